@@ -0,0 +1,115 @@
+//! Sending commands to `bspwm`, rather than just observing it.
+//!
+//! `bspc` itself is a thin wrapper that writes its argument vector to `bspwm`'s socket and
+//! prints back whatever the daemon replies with. `Connection` and `send_command()` do the
+//! same thing directly, so callers can drive `node -f`, `desktop -l`, `monitor -s`, and so
+//! on from the same library that parses the report line.
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+use crate::default_socket_path;
+
+/// Sends a single command to `bspwm` over its socket and returns the daemon's reply.
+///
+/// This resolves the default socket path and connects fresh for the one command; to send
+/// several commands, reuse a `Connection` instead of resolving the path each time.
+pub fn send_command(args: &[&str]) -> io::Result<String> {
+    Connection::new(None)?.send(args)
+}
+
+/// A `bspwm` socket endpoint, used to send commands and read back replies.
+///
+/// `bspwm` closes the socket after replying to a command, the same one-shot model `bspc`
+/// itself uses, so `send()` opens a fresh connection for every call rather than keeping
+/// one open across calls.
+pub struct Connection {
+    path: PathBuf,
+}
+
+impl Connection {
+    /// Resolves `bspwm`'s socket path to send commands against. `path` is resolved the
+    /// same way `status_from_socket()` resolves it.
+    pub fn new(path: Option<&Path>) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => default_socket_path()?,
+        };
+
+        Ok(Connection { path })
+    }
+
+    /// Sends `args` as a `bspc`-style command and returns the daemon's reply.
+    ///
+    /// `bspwm` replies with a leading byte that's either empty (success) or `\x07`
+    /// followed by an error message, which is surfaced as an `Err`.
+    pub fn send(&mut self, args: &[&str]) -> io::Result<String> {
+        let mut stream = UnixStream::connect(&self.path)?;
+        stream.write_all(&encode_message(args))?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply)?;
+
+        parse_reply(&reply)
+    }
+}
+
+/// Encodes a `bspc`-style argument vector as the NUL-separated byte string `bspwm` expects
+/// on its socket.
+fn encode_message(args: &[&str]) -> Vec<u8> {
+    let mut message = Vec::new();
+
+    for arg in args {
+        message.extend_from_slice(arg.as_bytes());
+        message.push(0);
+    }
+
+    message
+}
+
+/// Parses `bspwm`'s reply: a leading `\x07` byte means the rest is an error message,
+/// otherwise the whole reply is the success message (often empty).
+fn parse_reply(reply: &[u8]) -> io::Result<String> {
+    if reply.first() == Some(&0x07) {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&reply[1..]).into_owned(),
+        ))
+    } else {
+        Ok(String::from_utf8_lossy(reply).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_args_as_nul_separated() {
+        assert_eq!(encode_message(&["node", "-f", "next"]), b"node\0-f\0next\0");
+    }
+
+    #[test]
+    fn encodes_no_args_as_empty_message() {
+        assert_eq!(encode_message(&[]), b"");
+    }
+
+    #[test]
+    fn empty_reply_is_success() {
+        assert_eq!(parse_reply(b"").unwrap(), "");
+    }
+
+    #[test]
+    fn non_error_reply_is_success() {
+        assert_eq!(parse_reply(b"0x00000001").unwrap(), "0x00000001");
+    }
+
+    #[test]
+    fn bell_prefixed_reply_is_an_error() {
+        let mut reply = vec![0x07];
+        reply.extend_from_slice(b"failure: can't find node");
+
+        let err = parse_reply(&reply).unwrap_err();
+        assert_eq!(err.to_string(), "failure: can't find node");
+    }
+}
@@ -5,12 +5,12 @@
 //!
 //! List the names of the current monitors and the desktops that are on them:
 //!
-//! ````
+//! ````no_run
 //! extern crate bspwm_info;
 //! use bspwm_info::*;
 //!
 //! fn main() {
-//!     let current_info = status().next().unwrap();
+//!     let current_info = status().next().unwrap().unwrap();
 //!     for monitor in current_info.monitors {
 //!         println!("{}:", monitor.name);
 //!         for desktop in monitor.desktops {
@@ -20,41 +20,94 @@
 //! }
 //! ````
 //!
-//! # To-Do
-//!
-//! * Use a different command (probably `bspc wm -d`) to obtain more information than
-//!     `bspc subscribe report` provides.
-//! * Communicate with `bspwm`'s socket directly rather than wrap the `bspc` command.
-//!     Something like `fn status(path: Option<&Path>)`, where `Some(path)` specifies
-//!     the socket location and `None` uses the default location as specified in
-//!     `bspwm`'s man page.
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+#[cfg(feature = "async")]
+pub mod stream;
+
+#[cfg(feature = "async")]
+pub use stream::{status_stream, WmStream};
+
+#[cfg(feature = "state")]
+pub mod state;
+
+#[cfg(feature = "state")]
+pub use state::{dump_state, dump_state_from_socket, WmState};
 
-use std::io::{self, BufRead, BufReader};
-use std::process::{Command, Stdio, ChildStdout};
+pub mod command;
+
+pub use command::{send_command, Connection};
+
+pub mod watch;
+
+pub use watch::{subscribe_all, Watcher};
 
 /// Creates a new `WmInfo`
 pub fn status() -> WmInfo {
     WmInfo::new()
 }
 
-/// An iterator over `WmRoot`s that is created with the `status()` function
+/// Creates a new `WmInfo` that talks to `bspwm`'s socket directly instead of spawning `bspc`.
+///
+/// `path` specifies the socket location; `None` resolves it the same way `bspc` does: the
+/// `BSPWM_SOCKET` environment variable if it's set, otherwise the default location derived
+/// from `$DISPLAY` as described in `bspwm`'s man page.
+pub fn status_from_socket(path: Option<&Path>) -> io::Result<WmInfo> {
+    WmInfo::from_socket(path)
+}
+
+/// An iterator over `WmRoot`s that is created with the `status()` or `status_from_socket()`
+/// functions
 ///
-/// Internally, it holds a `BufReader` that collects output from `bspc subscribe report`.
-/// Each call to `next()` blocks until `bspc` prints a new line. That line
-/// is then parsed into a `WmRoot`
+/// Internally, it holds a `BufRead` that collects output from either `bspc subscribe report`
+/// or a direct connection to `bspwm`'s socket. Each call to `next()` blocks until a new line
+/// is available. That line is then parsed into a `WmRoot`
 pub struct WmInfo {
     buffer: String,
-    child_stdout: BufReader<ChildStdout>,
+    reader: Box<dyn BufRead>,
+    // Kept alive, and reaped on drop, so the `bspc` backend doesn't leave a zombie
+    // process behind. `None` when `reader` is backed by a socket instead.
+    child: Option<Child>,
 }
 
 impl WmInfo {
     fn new() -> Self {
-        let output = Command::new("bspc").args(&["subscribe", "report"]).stdout(Stdio::piped()).spawn().expect("Failed to run bspc. Is bspwm installed?");
-        let stdout = output.stdout.expect("Failed to get bspc's stdout");
+        let mut child = Command::new("bspc").args(["subscribe", "report"]).stdout(Stdio::piped()).spawn().expect("Failed to run bspc. Is bspwm installed?");
+        let stdout = child.stdout.take().expect("Failed to get bspc's stdout");
 
         WmInfo {
             buffer: String::new(),
-            child_stdout: BufReader::new(stdout),
+            reader: Box::new(BufReader::new(stdout)),
+            child: Some(child),
+        }
+    }
+
+    fn from_socket(path: Option<&Path>) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => default_socket_path()?,
+        };
+
+        let mut stream = UnixStream::connect(&path)?;
+        stream.write_all(b"subscribe\0report\0")?;
+
+        Ok(WmInfo {
+            buffer: String::new(),
+            reader: Box::new(BufReader::new(stream)),
+            child: None,
+        })
+    }
+}
+
+impl Drop for WmInfo {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
         }
     }
 }
@@ -65,7 +118,7 @@ impl Iterator for WmInfo {
     fn next(&mut self) -> Option<io::Result<WmRoot>> {
         self.buffer.clear();
 
-        match self.child_stdout.read_line(&mut self.buffer) {
+        match self.reader.read_line(&mut self.buffer) {
             Ok(i) => {
                 if i > 0 {
                     Some(Ok(parse_line(&self.buffer)))
@@ -80,11 +133,88 @@ impl Iterator for WmInfo {
     }
 }
 
-fn parse_line(line: &str) -> WmRoot {
+/// Resolves the path of `bspwm`'s socket the same way `bspc` does: the `BSPWM_SOCKET`
+/// environment variable if it's set, otherwise `/tmp/bspwm<host>_<display>_<screen>-socket`,
+/// where `host`, `display` and `screen` come from parsing `$DISPLAY` the way
+/// `xcb_parse_display()` does (`[host]:display[.screen]`, screen defaulting to `0`).
+pub(crate) fn default_socket_path() -> io::Result<PathBuf> {
+    Ok(socket_path_from_env(
+        env::var("BSPWM_SOCKET").ok(),
+        env::var("DISPLAY").ok(),
+    ))
+}
+
+fn socket_path_from_env(bspwm_socket: Option<String>, display: Option<String>) -> PathBuf {
+    if let Some(socket) = bspwm_socket {
+        return PathBuf::from(socket);
+    }
+
+    let display = display.unwrap_or_default();
+    let (host, display_num, screen_num) = parse_display(&display);
+
+    PathBuf::from(format!("/tmp/bspwm{}_{}_{}-socket", host, display_num, screen_num))
+}
+
+/// Splits a `$DISPLAY`-style string (`[host]:display[.screen]`) into its `host`, `display`
+/// and `screen` components, defaulting `display`/`screen` to `"0"` when absent.
+fn parse_display(display: &str) -> (&str, &str, &str) {
+    let (host, rest) = match display.find(':') {
+        Some(i) => (&display[..i], &display[i + 1..]),
+        None => ("", display),
+    };
+
+    let (display_num, screen_num) = match rest.find('.') {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, ""),
+    };
+
+    (
+        host,
+        if display_num.is_empty() { "0" } else { display_num },
+        if screen_num.is_empty() { "0" } else { screen_num },
+    )
+}
+
+#[cfg(test)]
+mod socket_path_tests {
+    use super::*;
+
+    #[test]
+    fn bare_display_and_screen_default_to_zero() {
+        let path = socket_path_from_env(None, Some(":0".to_string()));
+        assert_eq!(path, PathBuf::from("/tmp/bspwm_0_0-socket"));
+    }
+
+    #[test]
+    fn explicit_screen_is_used() {
+        let path = socket_path_from_env(None, Some(":1.2".to_string()));
+        assert_eq!(path, PathBuf::from("/tmp/bspwm_1_2-socket"));
+    }
+
+    #[test]
+    fn host_is_preserved() {
+        let path = socket_path_from_env(None, Some("remote:3.4".to_string()));
+        assert_eq!(path, PathBuf::from("/tmp/bspwmremote_3_4-socket"));
+    }
+
+    #[test]
+    fn missing_display_defaults_to_display_zero_screen_zero() {
+        let path = socket_path_from_env(None, None);
+        assert_eq!(path, PathBuf::from("/tmp/bspwm_0_0-socket"));
+    }
+
+    #[test]
+    fn bspwm_socket_env_var_overrides_display() {
+        let path = socket_path_from_env(Some("/custom/socket".to_string()), Some(":0".to_string()));
+        assert_eq!(path, PathBuf::from("/custom/socket"));
+    }
+}
+
+pub(crate) fn parse_line(line: &str) -> WmRoot {
     let mut monitors: Vec<Monitor> = Vec::new();
 
     for section in line[1..].split(":") {
-        let input = section.chars().nth(0).unwrap();
+        let input = section.chars().next().unwrap();
         match input {
             'M' | 'm' => { // monitor
                 monitors.push(
@@ -143,7 +273,7 @@ fn parse_line(line: &str) -> WmRoot {
         }
     }
 
-    WmRoot { monitors: monitors }
+    WmRoot { monitors }
 }
 
 /// A list of all the monitors that `bspwm` is aware of
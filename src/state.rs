@@ -0,0 +1,371 @@
+//! Rich window-manager state obtained from `bspc wm -d`, enabled via the `state` feature.
+//!
+//! `bspc subscribe report` (used by `WmInfo`) only reports desktop occupancy and focus;
+//! it says nothing about individual windows. `bspc wm -d` dumps the full internal tree as
+//! JSON instead, which this module deserializes with `serde` into typed structs that
+//! include per-window geometry, class/instance/title, and the binary tree `bspwm` tiles
+//! windows with.
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::default_socket_path;
+
+/// Dumps the full window-manager state by running `bspc wm -d` and deserializing its
+/// JSON output.
+pub fn dump_state() -> io::Result<WmState> {
+    let output = Command::new("bspc").args(["wm", "-d"]).output()?;
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Dumps the full window-manager state by sending `wm\0-d\0` over `bspwm`'s socket
+/// directly, rather than spawning `bspc`. `path` is resolved the same way
+/// `status_from_socket()` resolves it.
+pub fn dump_state_from_socket(path: Option<&Path>) -> io::Result<WmState> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => default_socket_path()?,
+    };
+
+    let mut stream = UnixStream::connect(&path)?;
+    stream.write_all(b"wm\0-d\0")?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+
+    serde_json::from_str(&reply).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// The root of the tree returned by `bspc wm -d`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WmState {
+    pub focused_monitor_id: u32,
+    pub clients_count: u32,
+    pub monitors: Vec<Monitor>,
+}
+
+impl WmState {
+    /// Flattens the tree into every `Client` it contains, so callers can enumerate actual
+    /// windows instead of walking `Node`s by hand.
+    pub fn windows(&self) -> Vec<&Client> {
+        let mut windows = Vec::new();
+
+        for monitor in &self.monitors {
+            for desktop in &monitor.desktops {
+                if let Some(root) = &desktop.root {
+                    root.collect_clients(&mut windows);
+                }
+            }
+        }
+
+        windows
+    }
+
+    /// Queries `_NET_WM_NAME` for every window and fills in each `Client`'s `title`.
+    ///
+    /// `bspc wm -d` doesn't include window titles, so this opens a single connection to
+    /// the X server and makes one extra round trip per window after the tree has been
+    /// deserialized.
+    pub fn fetch_titles(&mut self) -> io::Result<()> {
+        let (conn, _screen_num) = xcb::Connection::connect(None).map_err(io::Error::other)?;
+        let net_wm_name = xcb::intern_atom(&conn, false, "_NET_WM_NAME")
+            .get_reply()
+            .map_err(io::Error::other)?
+            .atom();
+        let utf8_string = xcb::intern_atom(&conn, false, "UTF8_STRING")
+            .get_reply()
+            .map_err(io::Error::other)?
+            .atom();
+
+        for monitor in &mut self.monitors {
+            for desktop in &mut monitor.desktops {
+                if let Some(root) = &mut desktop.root {
+                    root.fetch_titles(&conn, net_wm_name, utf8_string)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Monitor {
+    pub name: String,
+    pub id: u32,
+    pub wired: bool,
+    pub sticky_count: u32,
+    pub window_gap: i32,
+    pub border_width: u32,
+    pub focused_desktop_id: u32,
+    pub padding: Padding,
+    pub rectangle: Rectangle,
+    pub desktops: Vec<Desktop>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Desktop {
+    pub name: String,
+    pub id: u32,
+    pub layout: Layout,
+    pub user_layout: Layout,
+    pub window_gap: i32,
+    pub border_width: u32,
+    pub focused_node_id: Option<u32>,
+    pub padding: Padding,
+    pub root: Option<Node>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    Tiled,
+    Monocle,
+}
+
+/// A node in a desktop's binary tiling tree. Leaf nodes hold a `Client`; internal nodes
+/// split their `rectangle` between `first_child` and `second_child` according to
+/// `split_type` and `split_ratio`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    pub id: u32,
+    pub split_type: SplitType,
+    pub split_ratio: f64,
+    pub vacant: bool,
+    pub hidden: bool,
+    pub sticky: bool,
+    pub private: bool,
+    pub locked: bool,
+    pub rectangle: Rectangle,
+    pub first_child: Option<Box<Node>>,
+    pub second_child: Option<Box<Node>>,
+    pub client: Option<Client>,
+}
+
+impl Node {
+    fn collect_clients<'a>(&'a self, out: &mut Vec<&'a Client>) {
+        if let Some(client) = &self.client {
+            out.push(client);
+        }
+
+        if let Some(child) = &self.first_child {
+            child.collect_clients(out);
+        }
+
+        if let Some(child) = &self.second_child {
+            child.collect_clients(out);
+        }
+    }
+
+    fn fetch_titles(
+        &mut self,
+        conn: &xcb::Connection,
+        net_wm_name: u32,
+        utf8_string: u32,
+    ) -> io::Result<()> {
+        if let Some(client) = &mut self.client {
+            client.title = fetch_net_wm_name(conn, self.id, net_wm_name, utf8_string)?;
+        }
+
+        if let Some(child) = &mut self.first_child {
+            child.fetch_titles(conn, net_wm_name, utf8_string)?;
+        }
+
+        if let Some(child) = &mut self.second_child {
+            child.fetch_titles(conn, net_wm_name, utf8_string)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Looks up a window's `_NET_WM_NAME` property over an already-open `xcb::Connection`.
+///
+/// A node's `id` in `bspwm` is the X window ID, so it can be queried directly.
+fn fetch_net_wm_name(
+    conn: &xcb::Connection,
+    window: u32,
+    net_wm_name: u32,
+    utf8_string: u32,
+) -> io::Result<Option<String>> {
+    let cookie = xcb::get_property(conn, false, window, net_wm_name, utf8_string, 0, u32::MAX);
+    let reply = cookie.get_reply().map_err(io::Error::other)?;
+
+    if reply.value_len() == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(String::from_utf8_lossy(reply.value()).into_owned()))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitType {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Client {
+    pub class_name: String,
+    pub instance_name: String,
+    /// Not part of `bspc wm -d`'s own output; populated separately from the window's
+    /// `_NET_WM_NAME` property, since `bspwm` itself doesn't track window titles.
+    #[serde(skip)]
+    pub title: Option<String>,
+    pub border_width: u32,
+    pub urgent: bool,
+    pub shown: bool,
+    pub tiled_rectangle: Rectangle,
+    pub floating_rectangle: Rectangle,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct Rectangle {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct Padding {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-constructed to match the fields this module's structs declare, not a captured
+    // `bspc wm -d` trace — it hasn't been cross-checked against real `bspwm` output, so a
+    // pass here only proves the deserialization logic is internally consistent, not that
+    // the schema matches the daemon. One monitor split into two desktops: an empty "II"
+    // and a tiled "I" holding two windows.
+    const SAMPLE: &str = r#"
+    {
+        "focusedMonitorId": 1,
+        "clientsCount": 2,
+        "monitors": [
+            {
+                "name": "eDP-1",
+                "id": 1,
+                "wired": true,
+                "stickyCount": 0,
+                "windowGap": 6,
+                "borderWidth": 2,
+                "focusedDesktopId": 10,
+                "padding": {"top": 0, "right": 0, "bottom": 0, "left": 0},
+                "rectangle": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+                "desktops": [
+                    {
+                        "name": "I",
+                        "id": 10,
+                        "layout": "tiled",
+                        "userLayout": "tiled",
+                        "windowGap": 6,
+                        "borderWidth": 2,
+                        "focusedNodeId": 101,
+                        "padding": {"top": 0, "right": 0, "bottom": 0, "left": 0},
+                        "root": {
+                            "id": 100,
+                            "splitType": "horizontal",
+                            "splitRatio": 0.5,
+                            "vacant": false,
+                            "hidden": false,
+                            "sticky": false,
+                            "private": false,
+                            "locked": false,
+                            "rectangle": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+                            "firstChild": {
+                                "id": 101,
+                                "splitType": "horizontal",
+                                "splitRatio": 0.5,
+                                "vacant": false,
+                                "hidden": false,
+                                "sticky": false,
+                                "private": false,
+                                "locked": false,
+                                "rectangle": {"x": 0, "y": 0, "width": 960, "height": 1080},
+                                "firstChild": null,
+                                "secondChild": null,
+                                "client": {
+                                    "className": "Alacritty",
+                                    "instanceName": "alacritty",
+                                    "borderWidth": 2,
+                                    "urgent": false,
+                                    "shown": true,
+                                    "tiledRectangle": {"x": 0, "y": 0, "width": 960, "height": 1080},
+                                    "floatingRectangle": {"x": 0, "y": 0, "width": 960, "height": 1080}
+                                }
+                            },
+                            "secondChild": {
+                                "id": 102,
+                                "splitType": "horizontal",
+                                "splitRatio": 0.5,
+                                "vacant": false,
+                                "hidden": false,
+                                "sticky": false,
+                                "private": false,
+                                "locked": false,
+                                "rectangle": {"x": 960, "y": 0, "width": 960, "height": 1080},
+                                "firstChild": null,
+                                "secondChild": null,
+                                "client": {
+                                    "className": "firefox",
+                                    "instanceName": "Navigator",
+                                    "borderWidth": 2,
+                                    "urgent": false,
+                                    "shown": true,
+                                    "tiledRectangle": {"x": 960, "y": 0, "width": 960, "height": 1080},
+                                    "floatingRectangle": {"x": 960, "y": 0, "width": 960, "height": 1080}
+                                }
+                            },
+                            "client": null
+                        }
+                    },
+                    {
+                        "name": "II",
+                        "id": 11,
+                        "layout": "tiled",
+                        "userLayout": "tiled",
+                        "windowGap": 6,
+                        "borderWidth": 2,
+                        "focusedNodeId": null,
+                        "padding": {"top": 0, "right": 0, "bottom": 0, "left": 0},
+                        "root": null
+                    }
+                ]
+            }
+        ]
+    }
+    "#;
+
+    #[test]
+    fn deserializes_bspc_wm_dump() {
+        let state: WmState = serde_json::from_str(SAMPLE).expect("sample should deserialize");
+
+        assert_eq!(state.focused_monitor_id, 1);
+        assert_eq!(state.monitors.len(), 1);
+        assert_eq!(state.monitors[0].desktops.len(), 2);
+
+        let windows = state.windows();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].class_name, "Alacritty");
+        assert_eq!(windows[1].class_name, "firefox");
+    }
+}
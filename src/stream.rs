@@ -0,0 +1,106 @@
+//! Async support, enabled via the `async` feature.
+//!
+//! Mirrors the blocking `WmInfo` iterator, but drives `bspc subscribe report` with
+//! `tokio::process` and hands lines back as a `futures::Stream` instead of blocking the
+//! calling thread on every `next()`.
+
+use std::io;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use tokio::io::{BufReader, Lines, AsyncBufReadExt};
+use tokio::process::{Child, ChildStdout, Command};
+
+use crate::{parse_line, WmRoot};
+
+/// Creates a `Stream` of `WmRoot`s by spawning `bspc subscribe report` under `tokio`.
+///
+/// Each item resolves as soon as `bspc` emits a new report line, without blocking the
+/// executor thread the way `WmInfo::next()` does.
+pub fn status_stream() -> io::Result<WmStream> {
+    WmStream::new()
+}
+
+/// A `futures::Stream` of `WmRoot`s, created with `status_stream()`.
+///
+/// The spawned `bspc` child is kept alive for as long as the stream is; it's killed when
+/// the stream is dropped.
+pub struct WmStream {
+    lines: Lines<BufReader<ChildStdout>>,
+    // Kept alive so `bspc` isn't reaped while the stream is still in use.
+    child: Child,
+}
+
+impl WmStream {
+    fn new() -> io::Result<Self> {
+        let mut child = Command::new("bspc")
+            .args(["subscribe", "report"])
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdout = child.stdout.take().expect("Failed to get bspc's stdout");
+
+        Ok(WmStream {
+            lines: BufReader::new(stdout).lines(),
+            child,
+        })
+    }
+}
+
+impl Stream for WmStream {
+    type Item = io::Result<WmRoot>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        Pin::new(&mut this.lines)
+            .poll_next_line(cx)
+            .map(line_to_item)
+    }
+}
+
+impl Drop for WmStream {
+    fn drop(&mut self) {
+        let _ = self.child.start_kill();
+    }
+}
+
+/// Maps a `tokio::io::Lines` poll result to the corresponding `Stream::Item`, parsing a
+/// line into a `WmRoot` the same way `WmInfo`'s blocking `Iterator` does.
+fn line_to_item(line: io::Result<Option<String>>) -> Option<io::Result<WmRoot>> {
+    match line {
+        Ok(Some(line)) => Some(Ok(parse_line(&line))),
+        Ok(None) => None,
+        Err(e) => Some(Err(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_report_line() {
+        let root = line_to_item(Ok(Some("WMprimary:Oone:Ftwo:LT".to_string())))
+            .expect("should produce an item")
+            .expect("should parse successfully");
+
+        assert_eq!(root.monitors[0].name, "primary");
+    }
+
+    #[test]
+    fn end_of_stream_yields_none() {
+        assert!(line_to_item(Ok(None)).is_none());
+    }
+
+    #[test]
+    fn io_errors_are_passed_through() {
+        let err = line_to_item(Err(io::Error::other("broken pipe")))
+            .expect("should produce an item")
+            .expect_err("should be an error");
+
+        assert_eq!(err.to_string(), "broken pipe");
+    }
+}
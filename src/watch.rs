@@ -0,0 +1,99 @@
+//! Callback-driven event watching, for daemons (e.g. auto-tiling) that want to react to
+//! window-manager events without hand-rolling their own `next()` loop around `WmInfo`.
+
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use crate::{default_socket_path, parse_line, WmRoot};
+
+/// Subscribes to `report` plus the `node_add`, `node_remove`, `node_transfer`,
+/// `node_state`, `desktop_add`, `desktop_remove`, `desktop_focus` and `desktop_transfer`
+/// events, rather than just `report` the way `WmInfo` does. Other events (such as
+/// `node_focus` or `desktop_layout`) are not subscribed to.
+///
+/// `bspwm` emits a fresh `report` line after every subscribed event that changes the
+/// tree, so `Watcher::watch()`'s callback runs once per event of interest even though
+/// only `report` lines are parsed into a `WmRoot`.
+pub fn subscribe_all(path: Option<&Path>) -> io::Result<Watcher> {
+    Watcher::new(path)
+}
+
+/// A subscription to `bspwm`'s events, created with `subscribe_all()`.
+pub struct Watcher {
+    buffer: String,
+    reader: BufReader<UnixStream>,
+}
+
+impl Watcher {
+    fn new(path: Option<&Path>) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => default_socket_path()?,
+        };
+
+        let mut stream = UnixStream::connect(&path)?;
+        stream.write_all(
+            b"subscribe\0report\0node_add\0node_remove\0node_transfer\0node_state\0\
+              desktop_add\0desktop_remove\0desktop_focus\0desktop_transfer\0",
+        )?;
+
+        Ok(Watcher {
+            buffer: String::new(),
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Runs `callback` once for every `WmRoot` snapshot `bspwm` reports, blocking the
+    /// calling thread between events.
+    ///
+    /// This is the reusable core of an auto-tiling daemon: for example, a callback can
+    /// inspect `WmRoot::monitors` and issue `send_command(&["desktop", &name, "-l",
+    /// "monocle"])` or `"tiled"` for each desktop, in response to each snapshot. A
+    /// `WmRoot` only says whether a desktop is occupied, not how many nodes it holds.
+    pub fn watch<F>(mut self, mut callback: F) -> io::Result<()>
+    where
+        F: FnMut(&WmRoot),
+    {
+        loop {
+            self.buffer.clear();
+
+            if self.reader.read_line(&mut self.buffer)? == 0 {
+                return Ok(());
+            }
+
+            if let Some(root) = parse_event_line(&self.buffer) {
+                callback(&root);
+            }
+        }
+    }
+}
+
+/// Parses a single line from `bspwm`'s event socket into a `WmRoot`, if it's a `report`
+/// line (the only event type that carries a `WmRoot` snapshot). Other subscribed events
+/// (`node_add`, `desktop_focus`, ...) are dropped here, since they only exist to prompt
+/// `bspwm` to emit a fresh `report` line.
+fn parse_event_line(line: &str) -> Option<WmRoot> {
+    if line.starts_with('W') {
+        Some(parse_line(line))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lines_are_parsed() {
+        let root = parse_event_line("WMprimary:Oone:Ftwo:LT").expect("report line should parse");
+        assert_eq!(root.monitors[0].name, "primary");
+    }
+
+    #[test]
+    fn non_report_lines_are_ignored() {
+        assert!(parse_event_line("node_add 0x1 0x2 0x3\n").is_none());
+    }
+}